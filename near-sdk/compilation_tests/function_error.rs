@@ -1,4 +1,10 @@
 //! Testing FunctionError macro.
+//!
+//! NOTE: structured (non-`Display`) error serialization for `#[return_result]`, e.g.
+//! `#[return_result(serializer = "borsh")]`, is NOT implemented here. It requires changes to the
+//! `near-sdk-macros` crate, which isn't part of this tree, so only the existing `Display`-panic
+//! path below is exercised. Track the structured-serializer feature as a separate follow-up
+//! against `near-sdk-macros` rather than this file.
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::{near_bindgen, FunctionError};
@@ -0,0 +1,252 @@
+//! A compact bitset implemented on a trie, packing 64 bits into each stored word rather than
+//! using one trie entry per element.
+
+use crate::collections::append_slice;
+use crate::{env, CacheCell, CacheEntry, EntryState, IntoStorageKey};
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::collections::{btree_map::Entry, BTreeMap};
+use std::ptr::NonNull;
+
+const ERR_ELEMENT_DESERIALIZATION: &[u8] = b"Cannot deserialize element";
+const ERR_ELEMENT_SERIALIZATION: &[u8] = b"Cannot serialize element";
+
+const BITS_PER_WORD: u64 = u64::BITS as u64;
+
+/// A bitset that stores its content on the trie, packing bits densely into `u64` words keyed by
+/// `prefix || (i / 64).to_le_bytes()` rather than storing one trie entry per bit.
+///
+/// A word that has never been written is treated as all-zero, so bits can be set at arbitrary,
+/// sparse indices without pre-declaring a capacity. This makes [`Bitset`] a natural free-slot
+/// allocator for tracking which indices of a [`Vector`](super::vec::Vector) or map-style
+/// collection are reusable.
+///
+/// This implementation will cache all changes and loads and only updates values that are changed
+/// in storage after it's dropped through it's [`Drop`] implementation.
+///
+/// TODO examples
+#[derive(BorshSerialize, BorshDeserialize)]
+#[cfg_attr(not(feature = "expensive-debug"), derive(Debug))]
+pub struct Bitset {
+    len: u64,
+    prefix: Vec<u8>,
+    #[borsh_skip]
+    /// Cache for loaded and modified words, keyed by word index (`i / 64`).
+    cache: CacheCell<BTreeMap<u64, Box<CacheEntry<u64>>>>,
+}
+
+impl Bitset {
+    /// Returns one past the highest bit index that has been set, cleared or toggled since the
+    /// bitset was created or last reset with [`Bitset::clear`].
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no bit has been set, cleared or toggled since the bitset was created or
+    /// last reset with [`Bitset::clear`].
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Create new bitset with zero bits. Use `prefix` as a unique identifier on the trie.
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self { len: 0, prefix: prefix.into_storage_key(), cache: Default::default() }
+    }
+
+    fn word_to_lookup_key(&self, word: u64) -> Vec<u8> {
+        append_slice(&self.prefix, &word.to_le_bytes()[..])
+    }
+
+    fn word_count(&self) -> u64 {
+        (self.len + BITS_PER_WORD - 1) / BITS_PER_WORD
+    }
+
+    /// Removes all bits from the collection, clearing storage for every word that was touched.
+    pub fn clear(&mut self) {
+        for word in 0..self.word_count() {
+            env::storage_remove(&self.word_to_lookup_key(word));
+        }
+        self.len = 0;
+        self.cache.as_inner_mut().clear();
+    }
+
+    /// Flushes the cache and writes all modified words to storage.
+    fn flush(&mut self) {
+        for (k, v) in self.cache.as_inner_mut().iter_mut() {
+            if v.is_modified() {
+                let key = append_slice(&self.prefix, &k.to_le_bytes()[..]);
+                match v.value().as_ref() {
+                    Some(modified) => {
+                        env::storage_write(&key, &Self::serialize_word(modified));
+                    }
+                    None => {
+                        env::storage_remove(&key);
+                    }
+                }
+
+                v.replace_state(EntryState::Cached);
+            }
+        }
+    }
+
+    fn serialize_word(word: &u64) -> Vec<u8> {
+        word.try_to_vec().unwrap_or_else(|_| env::panic(ERR_ELEMENT_SERIALIZATION))
+    }
+
+    fn deserialize_word(raw_word: &[u8]) -> u64 {
+        u64::try_from_slice(&raw_word).unwrap_or_else(|_| env::panic(ERR_ELEMENT_DESERIALIZATION))
+    }
+
+    /// Loads a word from storage into cache, defaulting to zero if it is absent.
+    /// This function must be unsafe because it requires modifying the cache with an immutable
+    /// reference.
+    unsafe fn load(&self, word: u64) -> NonNull<CacheEntry<u64>> {
+        match self.cache.get_ptr().as_mut().entry(word) {
+            Entry::Occupied(mut occupied) => NonNull::from(&mut **occupied.get_mut()),
+            Entry::Vacant(vacant) => {
+                let value = env::storage_read(&self.word_to_lookup_key(word))
+                    .map(|v| Self::deserialize_word(&v))
+                    .unwrap_or(0);
+                NonNull::from(&mut **vacant.insert(Box::new(CacheEntry::new_cached(Some(value)))))
+            }
+        }
+    }
+
+    /// Loads a word from storage into cache, and returns a mutable reference to the loaded word.
+    /// This function is safe because a mutable reference of self is used.
+    fn load_mut(&mut self, word: u64) -> &mut CacheEntry<u64> {
+        // * SAFETY: A mutable reference can be returned here because it references a value in a
+        //           `Box` and no other references should exist given function takes a mutable
+        //           reference.
+        unsafe { &mut *self.load(word).as_ptr() }
+    }
+
+    fn word(&self, word: u64) -> u64 {
+        *unsafe { &*self.load(word).as_ptr() }.value().as_ref().unwrap_or(&0)
+    }
+
+    /// Returns whether bit `i` is set.
+    pub fn get(&self, i: u64) -> bool {
+        let word = self.word(i / BITS_PER_WORD);
+        (word >> (i % BITS_PER_WORD)) & 1 == 1
+    }
+
+    fn grow_to(&mut self, len: u64) {
+        if len > self.len {
+            self.len = len;
+        }
+    }
+
+    /// Sets bit `i` to `1`.
+    pub fn set(&mut self, i: u64) {
+        self.grow_to(i + 1);
+
+        let word = i / BITS_PER_WORD;
+        let bit = i % BITS_PER_WORD;
+        let updated = self.word(word) | (1 << bit);
+        self.load_mut(word).replace(Some(updated));
+    }
+
+    /// Sets bit `i` to `0`.
+    ///
+    /// Named `clear_bit` rather than `clear` to avoid colliding with the whole-collection
+    /// [`Bitset::clear`], which follows the same `clear`-resets-everything convention as
+    /// [`Vector::clear`](super::vec::Vector::clear) and [`Deque::clear`](super::deque::Deque::clear).
+    pub fn clear_bit(&mut self, i: u64) {
+        self.grow_to(i + 1);
+
+        let word = i / BITS_PER_WORD;
+        let bit = i % BITS_PER_WORD;
+        let updated = self.word(word) & !(1 << bit);
+        self.load_mut(word).replace(Some(updated));
+    }
+
+    /// Flips bit `i`, returning its new value.
+    pub fn toggle(&mut self, i: u64) -> bool {
+        self.grow_to(i + 1);
+
+        let word = i / BITS_PER_WORD;
+        let bit = i % BITS_PER_WORD;
+        let updated = self.word(word) ^ (1 << bit);
+        self.load_mut(word).replace(Some(updated));
+        (updated >> bit) & 1 == 1
+    }
+
+    /// Returns the total number of bits that are set across the whole collection.
+    pub fn count_ones(&self) -> u32 {
+        (0..self.word_count()).map(|word| self.word(word).count_ones()).sum()
+    }
+}
+
+impl Drop for Bitset {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::VMContextBuilder;
+    use crate::testing_env;
+
+    fn set_env() {
+        testing_env!(VMContextBuilder::new().build());
+    }
+
+    #[test]
+    fn test_set_clear_toggle() {
+        set_env();
+        let mut bits = Bitset::new(b"b".to_vec());
+
+        assert!(!bits.get(5));
+        bits.set(5);
+        assert!(bits.get(5));
+        assert_eq!(bits.count_ones(), 1);
+
+        bits.clear_bit(5);
+        assert!(!bits.get(5));
+        assert_eq!(bits.count_ones(), 0);
+
+        assert!(bits.toggle(10));
+        assert!(bits.get(10));
+        assert!(!bits.toggle(10));
+        assert!(!bits.get(10));
+    }
+
+    #[test]
+    fn test_unwritten_bit_defaults_to_zero() {
+        set_env();
+        let bits = Bitset::new(b"b2".to_vec());
+        assert!(!bits.get(1_000));
+        assert_eq!(bits.count_ones(), 0);
+    }
+
+    #[test]
+    fn test_count_ones_spans_multiple_words() {
+        set_env();
+        let mut bits = Bitset::new(b"b3".to_vec());
+        bits.set(0);
+        bits.set(63);
+        bits.set(64);
+        bits.set(127);
+
+        assert_eq!(bits.count_ones(), 4);
+    }
+
+    #[test]
+    fn test_clear_resets_whole_collection() {
+        set_env();
+        let mut bits = Bitset::new(b"b4".to_vec());
+        bits.set(2);
+        bits.set(70);
+
+        bits.clear();
+        assert!(bits.is_empty());
+        assert!(!bits.get(2));
+        assert!(!bits.get(70));
+        assert_eq!(bits.count_ones(), 0);
+    }
+}
@@ -0,0 +1,281 @@
+//! A double-ended queue implemented on a trie. Unlike [`Vector`](super::vec::Vector), supports
+//! `O(1)` pushes and pops at both ends by moving a logical `head` cursor instead of shifting
+//! elements.
+
+use crate::collections::append_slice;
+use crate::{env, CacheCell, CacheEntry, EntryState, IntoStorageKey};
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::collections::{btree_map::Entry, BTreeMap};
+use std::ptr::NonNull;
+
+const ERR_INCONSISTENT_STATE: &[u8] = b"The collection is an inconsistent state. Did previous smart contract execution terminate unexpectedly?";
+const ERR_ELEMENT_DESERIALIZATION: &[u8] = b"Cannot deserialize element";
+const ERR_ELEMENT_SERIALIZATION: &[u8] = b"Cannot serialize element";
+const ERR_INDEX_OUT_OF_BOUNDS: &[u8] = b"Index out of bounds";
+
+fn expect_consistent_state<T>(val: Option<T>) -> T {
+    val.unwrap_or_else(|| env::panic(ERR_INCONSISTENT_STATE))
+}
+
+/// An implementation of a double-ended queue that stores its content on the trie.
+///
+/// Maps each logical position `k` to the trie key `prefix || (head + k).to_le_bytes()`, where
+/// `head` is a signed cursor that moves left on `push_front` and right on `pop_front`. Because
+/// the trie is sparse there is no fixed capacity or wraparound to manage, unlike a ring-buffer
+/// backed `VecDeque`.
+///
+/// This implementation will cache all changes and loads and only updates values that are changed
+/// in storage after it's dropped through it's [`Drop`] implementation.
+///
+/// TODO examples
+#[derive(BorshSerialize, BorshDeserialize)]
+#[cfg_attr(not(feature = "expensive-debug"), derive(Debug))]
+pub struct Deque<T>
+where
+    T: BorshSerialize,
+{
+    head: i64,
+    len: u32,
+    prefix: Vec<u8>,
+    #[borsh_skip]
+    /// Cache for loads and intermediate changes to the underlying deque, keyed by the physical
+    /// offset `head + k` rather than the logical index.
+    cache: CacheCell<BTreeMap<i64, Box<CacheEntry<T>>>>,
+}
+
+impl<T> Deque<T>
+where
+    T: BorshSerialize,
+{
+    /// Returns the number of elements in the deque, also referred to as its size.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Returns `true` if the deque contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Create new deque with zero elements. Use `prefix` as a unique identifier on the trie.
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self { head: 0, len: 0, prefix: prefix.into_storage_key(), cache: Default::default() }
+    }
+
+    fn offset_to_lookup_key(&self, offset: i64) -> Vec<u8> {
+        append_slice(&self.prefix, &offset.to_le_bytes()[..])
+    }
+
+    /// Removes all elements from the collection. This will remove all storage values for the
+    /// length of the [`Deque`].
+    pub fn clear(&mut self) {
+        for i in 0..self.len as i64 {
+            let lookup_key = self.offset_to_lookup_key(self.head + i);
+            env::storage_remove(&lookup_key);
+        }
+        self.head = 0;
+        self.len = 0;
+        self.cache.as_inner_mut().clear();
+    }
+
+    /// Flushes the cache and writes all modified values to storage.
+    fn flush(&mut self) {
+        for (k, v) in self.cache.as_inner_mut().iter_mut() {
+            if v.is_modified() {
+                let key = append_slice(&self.prefix, &k.to_le_bytes()[..]);
+                match v.value().as_ref() {
+                    Some(modified) => {
+                        env::storage_write(&key, &Self::serialize_element(modified));
+                    }
+                    None => {
+                        env::storage_remove(&key);
+                    }
+                }
+
+                v.replace_state(EntryState::Cached);
+            }
+        }
+    }
+
+    fn set(&mut self, offset: i64, value: Option<T>) {
+        match self.cache.as_inner_mut().entry(offset) {
+            Entry::Occupied(mut occupied) => {
+                occupied.get_mut().replace(value);
+            }
+            Entry::Vacant(vacant) => {
+                vacant.insert(Box::new(CacheEntry::new_modified(value)));
+            }
+        }
+    }
+
+    fn serialize_element(element: &T) -> Vec<u8> {
+        element.try_to_vec().unwrap_or_else(|_| env::panic(ERR_ELEMENT_SERIALIZATION))
+    }
+
+    /// Appends an element to the back of the deque.
+    pub fn push_back(&mut self, element: T) {
+        if self.len() >= u32::MAX {
+            env::panic(ERR_INDEX_OUT_OF_BOUNDS);
+        }
+
+        let offset = self.head + self.len as i64;
+        self.len += 1;
+        self.set(offset, Some(element));
+    }
+
+    /// Prepends an element to the front of the deque.
+    pub fn push_front(&mut self, element: T) {
+        if self.len() >= u32::MAX {
+            env::panic(ERR_INDEX_OUT_OF_BOUNDS);
+        }
+
+        self.head -= 1;
+        self.len += 1;
+        self.set(self.head, Some(element));
+    }
+}
+
+impl<T> Deque<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    fn deserialize_element(raw_element: &[u8]) -> T {
+        T::try_from_slice(&raw_element).unwrap_or_else(|_| env::panic(ERR_ELEMENT_DESERIALIZATION))
+    }
+
+    /// Loads value from storage into cache, if it does not already exist.
+    /// This function must be unsafe because it requires modifying the cache with an immutable
+    /// reference.
+    unsafe fn load(&self, offset: i64) -> NonNull<CacheEntry<T>> {
+        match self.cache.get_ptr().as_mut().entry(offset) {
+            Entry::Occupied(mut occupied) => NonNull::from(&mut **occupied.get_mut()),
+            Entry::Vacant(vacant) => {
+                let value = env::storage_read(&self.offset_to_lookup_key(offset))
+                    .map(|v| Self::deserialize_element(&v));
+                NonNull::from(&mut **vacant.insert(Box::new(CacheEntry::new_cached(value))))
+            }
+        }
+    }
+
+    /// Loads value from storage into cache, and returns a mutable reference to the loaded value.
+    /// This function is safe because a mutable reference of self is used.
+    fn load_mut(&mut self, offset: i64) -> &mut CacheEntry<T> {
+        // * SAFETY: A mutable reference can be returned here because it references a value in a
+        //           `Box` and no other references should exist given function takes a mutable
+        //           reference.
+        unsafe { &mut *self.load(offset).as_ptr() }
+    }
+
+    /// Returns a reference to the element at the front of the deque, or `None` if it is empty.
+    pub fn front(&self) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        unsafe { &*self.load(self.head).as_ptr() }.value().as_ref()
+    }
+
+    /// Returns a reference to the element at the back of the deque, or `None` if it is empty.
+    pub fn back(&self) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        let offset = self.head + (self.len - 1) as i64;
+        unsafe { &*self.load(offset).as_ptr() }.value().as_ref()
+    }
+
+    /// Removes the element at the front of the deque and returns it, or `None` if it is empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let offset = self.head;
+        self.head += 1;
+        self.len -= 1;
+
+        let popped_value = expect_consistent_state(self.load_mut(offset).replace(None));
+        Some(popped_value)
+    }
+
+    /// Removes the element at the back of the deque and returns it, or `None` if it is empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let offset = self.head + (self.len - 1) as i64;
+        self.len -= 1;
+
+        let popped_value = expect_consistent_state(self.load_mut(offset).replace(None));
+        Some(popped_value)
+    }
+}
+
+impl<T> Drop for Deque<T>
+where
+    T: BorshSerialize,
+{
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::VMContextBuilder;
+    use crate::testing_env;
+
+    fn set_env() {
+        testing_env!(VMContextBuilder::new().build());
+    }
+
+    #[test]
+    fn test_push_pop_both_ends() {
+        set_env();
+        let mut deque: Deque<i32> = Deque::new(b"d".to_vec());
+        deque.push_back(1);
+        deque.push_back(2);
+        deque.push_front(0);
+
+        assert_eq!(deque.len(), 3);
+        assert_eq!(deque.front(), Some(&0));
+        assert_eq!(deque.back(), Some(&2));
+
+        assert_eq!(deque.pop_front(), Some(0));
+        assert_eq!(deque.pop_back(), Some(2));
+        assert_eq!(deque.pop_front(), Some(1));
+        assert_eq!(deque.pop_front(), None);
+        assert!(deque.is_empty());
+    }
+
+    #[test]
+    fn test_head_can_go_negative() {
+        set_env();
+        let mut deque: Deque<i32> = Deque::new(b"d2".to_vec());
+        deque.push_back(1);
+        for v in (2..=5).rev() {
+            deque.push_front(v);
+        }
+
+        let mut collected = Vec::new();
+        while let Some(v) = deque.pop_front() {
+            collected.push(v);
+        }
+        assert_eq!(collected, vec![2, 3, 4, 5, 1]);
+    }
+
+    #[test]
+    fn test_empty_deque() {
+        set_env();
+        let mut deque: Deque<i32> = Deque::new(b"d3".to_vec());
+        assert!(deque.is_empty());
+        assert_eq!(deque.front(), None);
+        assert_eq!(deque.back(), None);
+        assert_eq!(deque.pop_front(), None);
+        assert_eq!(deque.pop_back(), None);
+    }
+}
@@ -0,0 +1,179 @@
+//! A binary max-heap implemented on a trie. Layers the standard array-based heap layout on top
+//! of the existing [`Vector`], so only the elements on the affected root-to-leaf path are ever
+//! loaded instead of the whole collection.
+
+use crate::collections::Vector;
+use crate::IntoStorageKey;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// An implementation of a priority queue that stores its content on the trie as a
+/// binary max-heap.
+///
+/// Elements are kept in index order `0..len` where the element at `i` has children at `2i+1`
+/// and `2i+2`, exactly as in a standard array-backed heap. Because it is built on [`Vector`],
+/// `push` and `pop` reuse its lazy load/flush-on-drop cache and only touch `O(log n)` elements.
+///
+/// TODO examples
+#[derive(BorshSerialize, BorshDeserialize)]
+#[cfg_attr(not(feature = "expensive-debug"), derive(Debug))]
+pub struct Heap<T>
+where
+    T: Ord + BorshSerialize,
+{
+    elements: Vector<T>,
+}
+
+impl<T> Heap<T>
+where
+    T: Ord + BorshSerialize,
+{
+    /// Returns the number of elements in the heap.
+    pub fn len(&self) -> u32 {
+        self.elements.len()
+    }
+
+    /// Returns `true` if the heap contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Create new heap with zero elements. Use `prefix` as a unique identifier on the trie.
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self { elements: Vector::new(prefix) }
+    }
+
+    /// Removes all elements from the collection.
+    pub fn clear(&mut self) {
+        self.elements.clear()
+    }
+}
+
+impl<T> Heap<T>
+where
+    T: Ord + BorshSerialize + BorshDeserialize,
+{
+    /// Returns a reference to the greatest element in the heap, or `None` if it is empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.elements.get(0)
+    }
+
+    fn parent(i: u32) -> u32 {
+        (i - 1) / 2
+    }
+
+    fn children(i: u32) -> (u32, u32) {
+        (2 * i + 1, 2 * i + 2)
+    }
+
+    /// Returns whether the element at index `a` is greater than the element at index `b`.
+    fn greater(&self, a: u32, b: u32) -> bool {
+        self.elements.get(a) > self.elements.get(b)
+    }
+
+    /// Pushes an element onto the heap, then sifts it up until the max-heap property holds.
+    pub fn push(&mut self, element: T) {
+        self.elements.push(element);
+
+        let mut i = self.elements.len() - 1;
+        while i > 0 {
+            let parent = Self::parent(i);
+            if self.greater(i, parent) {
+                self.elements.swap(i, parent);
+                i = parent;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Removes the greatest element from the heap and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let len = self.elements.len();
+        if len == 0 {
+            return None;
+        }
+
+        let last = len - 1;
+        if last != 0 {
+            self.elements.swap(0, last);
+        }
+        let popped = self.elements.pop();
+
+        if !self.elements.is_empty() {
+            self.sift_down(0);
+        }
+        popped
+    }
+
+    /// Sifts the element at index `i` down by repeatedly swapping it with the larger of its
+    /// children until the max-heap property holds.
+    fn sift_down(&mut self, mut i: u32) {
+        loop {
+            let (left, right) = Self::children(i);
+            let len = self.elements.len();
+
+            let mut largest = i;
+            if left < len && self.greater(left, largest) {
+                largest = left;
+            }
+            if right < len && self.greater(right, largest) {
+                largest = right;
+            }
+            if largest == i {
+                break;
+            }
+
+            self.elements.swap(i, largest);
+            i = largest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::VMContextBuilder;
+    use crate::testing_env;
+
+    fn set_env() {
+        testing_env!(VMContextBuilder::new().build());
+    }
+
+    #[test]
+    fn test_push_pop_is_sorted_descending() {
+        set_env();
+        let mut heap: Heap<i32> = Heap::new(b"h".to_vec());
+        for v in [5, 1, 8, 3, 9, 2, 8] {
+            heap.push(v);
+        }
+
+        let mut popped = Vec::new();
+        while let Some(v) = heap.pop() {
+            popped.push(v);
+        }
+        assert_eq!(popped, vec![9, 8, 8, 5, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_peek_does_not_remove() {
+        set_env();
+        let mut heap: Heap<i32> = Heap::new(b"h2".to_vec());
+        heap.push(4);
+        heap.push(7);
+
+        assert_eq!(heap.peek(), Some(&7));
+        assert_eq!(heap.len(), 2);
+    }
+
+    #[test]
+    fn test_empty_heap() {
+        set_env();
+        let mut heap: Heap<i32> = Heap::new(b"h3".to_vec());
+        assert!(heap.is_empty());
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.pop(), None);
+    }
+}
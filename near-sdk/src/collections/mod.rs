@@ -0,0 +1,22 @@
+//! Collections that offer an alternative to standard containers from `std::collections::*` by
+//! utilizing the underlying blockchain trie storage more efficiently.
+//!
+//! For example, data structures like [`Vector`] and [`Deque`] won't load all of their
+//! content into memory. Instead, a small amount of data is kept in memory and everything else
+//! stays on the trie, loading and flushing only the parts that are actually touched.
+
+pub mod bitset;
+pub mod deque;
+pub mod heap;
+pub mod vec;
+
+pub use bitset::Bitset;
+pub use deque::Deque;
+pub use heap::Heap;
+pub use vec::Vector;
+
+/// Concatenates a storage prefix with a suffix, used to compute a per-element trie key from a
+/// collection's prefix.
+pub(crate) fn append_slice(id: &[u8], chunk: &[u8]) -> Vec<u8> {
+    [id, chunk].concat()
+}
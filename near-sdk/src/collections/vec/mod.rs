@@ -7,7 +7,9 @@ mod iter;
 use crate::collections::append_slice;
 use crate::{env, CacheCell, CacheEntry, EntryState, IntoStorageKey};
 use borsh::{BorshDeserialize, BorshSerialize};
+use std::cmp::Ordering;
 use std::collections::{btree_map::Entry, BTreeMap};
+use std::ops::{Bound, RangeBounds};
 use std::ptr::NonNull;
 
 const ERR_INCONSISTENT_STATE: &[u8] = b"The collection is an inconsistent state. Did previous smart contract execution terminate unexpectedly?";
@@ -183,7 +185,7 @@ where
         unsafe { &*self.load(index).as_ptr() }.value().as_ref()
     }
 
-    fn swap(&mut self, a: u32, b: u32) {
+    pub(crate) fn swap(&mut self, a: u32, b: u32) {
         if a >= self.len() || b >= self.len() {
             env::panic(ERR_INDEX_OUT_OF_BOUNDS);
         }
@@ -236,4 +238,330 @@ where
             Some(popped_value)
         }
     }
+
+    /// Binary searches this vector, which is assumed to be sorted by a projected key, for the
+    /// element for which `f` returns [`Ordering::Equal`].
+    ///
+    /// If the vector is not sorted by the order induced by `f`, the result is unspecified and
+    /// meaningless. Only the `O(log n)` probed elements are loaded through the cache, rather
+    /// than scanning the whole vector.
+    ///
+    /// If an element matching `f` is found then [`Ok`] is returned, containing the index of the
+    /// matching element. If there are multiple matches, then any one of the matches could be
+    /// returned. If no match is found then [`Err`] is returned, containing the index where a
+    /// matching element could be inserted while maintaining sorted order.
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<u32, u32>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        let mut lo = 0u32;
+        let mut hi = self.len();
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let probe = expect_consistent_state(self.get(mid));
+            match f(probe) {
+                Ordering::Less => lo = mid + 1,
+                Ordering::Greater => hi = mid,
+                Ordering::Equal => return Ok(mid),
+            }
+        }
+
+        Err(lo)
+    }
+
+    /// Shortens the vector, keeping the first `new_len` elements and removing the storage for
+    /// the rest. Does nothing if `new_len` is greater than the current length.
+    fn truncate(&mut self, new_len: u32) {
+        if new_len >= self.len {
+            return;
+        }
+        for i in new_len..self.len {
+            self.load_mut(i).replace(None);
+        }
+        self.len = new_len;
+    }
+
+    /// Retains only the elements for which `f` returns `true`, dropping the rest, in place.
+    ///
+    /// This is a single pass over the vector: a read cursor scans every element while a write
+    /// cursor tracks where the next kept element belongs, so the cost is one traversal rather
+    /// than repeated [`swap_remove`](Self::swap_remove) calls. Only the elements that actually
+    /// move incur a write.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = self.len();
+        let mut write = 0u32;
+
+        for read in 0..len {
+            let keep = f(expect_consistent_state(self.get(read)));
+            if keep {
+                if write != read {
+                    let value = expect_consistent_state(self.load_mut(read).replace(None));
+                    self.set(write, value);
+                }
+                write += 1;
+            }
+        }
+
+        self.truncate(write);
+    }
+
+    /// Removes the specified range from the vector, returning the removed elements as an
+    /// iterator. The elements after the range are shifted down to fill the gap.
+    ///
+    /// If the returned iterator is dropped before being fully consumed, the remaining elements
+    /// in the range are removed and the tail is still shifted down, leaving the vector in a
+    /// consistent state either way.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if the end point is
+    /// greater than the length of the vector.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T>
+    where
+        R: RangeBounds<u32>,
+    {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i + 1,
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => len,
+        };
+
+        if start > end || end > len {
+            env::panic(ERR_INDEX_OUT_OF_BOUNDS);
+        }
+
+        Drain { vec: self, cur: start, start, end, old_len: len }
+    }
+}
+
+/// An iterator that removes and yields a range of elements from a [`Vector`].
+///
+/// Returned by [`Vector::drain`]. Dropping this iterator before it is exhausted still finishes
+/// removing the range and shifts the tail down, mirroring the drop-guard invariant of
+/// [`Vec::drain`](std::vec::Vec::drain) / [`VecDeque::drain`](std::collections::VecDeque::drain).
+pub struct Drain<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    vec: &'a mut Vector<T>,
+    /// Index of the next element to yield, advances towards `end`.
+    cur: u32,
+    /// Index at which the drained range started.
+    start: u32,
+    /// One past the last index in the drained range.
+    end: u32,
+    /// Length of the vector before the drain started.
+    old_len: u32,
+}
+
+impl<'a, T> Iterator for Drain<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.cur >= self.end {
+            return None;
+        }
+
+        let idx = self.cur;
+        self.cur += 1;
+        Some(expect_consistent_state(self.vec.load_mut(idx).replace(None)))
+    }
+}
+
+impl<'a, T> Drop for Drain<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    fn drop(&mut self) {
+        // Remove whatever part of the range the iterator did not get to yield.
+        while self.cur < self.end {
+            self.vec.load_mut(self.cur).replace(None);
+            self.cur += 1;
+        }
+
+        // Shift the tail down to fill the gap left by the drained range. Nothing was actually
+        // drained if `start == end` (e.g. `drain(3..3)`), so the tail is already in place and
+        // there is nothing to move.
+        let tail_len = self.old_len - self.end;
+        if self.start != self.end {
+            for i in 0..tail_len {
+                let value = expect_consistent_state(self.vec.load_mut(self.end + i).replace(None));
+                self.vec.set(self.start + i, value);
+            }
+        }
+
+        self.vec.len = self.start + tail_len;
+    }
+}
+
+impl<T> Vector<T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    /// Binary searches this vector, which is assumed to be sorted, for `target`.
+    ///
+    /// If the vector is not sorted, the result is unspecified and meaningless. See
+    /// [`binary_search_by`](Self::binary_search_by) for more details.
+    pub fn binary_search(&self, target: &T) -> Result<u32, u32> {
+        self.binary_search_by(|probe| probe.cmp(target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::VMContextBuilder;
+    use crate::testing_env;
+
+    fn set_env() {
+        testing_env!(VMContextBuilder::new().build());
+    }
+
+    fn filled(prefix: &[u8], n: i32) -> Vector<i32> {
+        let mut v: Vector<i32> = Vector::new(prefix.to_vec());
+        for i in 0..n {
+            v.push(i);
+        }
+        v
+    }
+
+    #[test]
+    fn test_binary_search_found_and_not_found() {
+        set_env();
+        let v = filled(b"bs", 10); // 0, 1, .. 9, sorted.
+
+        assert_eq!(v.binary_search(&5), Ok(5));
+        assert_eq!(v.binary_search(&0), Ok(0));
+        assert_eq!(v.binary_search(&9), Ok(9));
+
+        // Not present (below the first and above the last element): Err holds the insertion point.
+        assert_eq!(v.binary_search(&-1), Err(0));
+        assert_eq!(v.binary_search(&10), Err(10));
+    }
+
+    #[test]
+    fn test_binary_search_with_duplicates_finds_a_match() {
+        set_env();
+        let mut v: Vector<i32> = Vector::new(b"bs2".to_vec());
+        for x in [1, 3, 3, 3, 5, 7] {
+            v.push(x);
+        }
+
+        let idx = v.binary_search(&3).unwrap();
+        assert_eq!(*v.get(idx).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_binary_search_empty_vector() {
+        set_env();
+        let v: Vector<i32> = Vector::new(b"bs3".to_vec());
+        assert_eq!(v.binary_search(&0), Err(0));
+    }
+
+    #[test]
+    fn test_binary_search_by_projected_key() {
+        set_env();
+        let mut v: Vector<(i32, &'static str)> = Vector::new(b"bs4".to_vec());
+        for pair in [(1, "a"), (3, "b"), (5, "c"), (7, "d")] {
+            v.push(pair);
+        }
+
+        let found = v.binary_search_by(|(key, _)| key.cmp(&5));
+        assert_eq!(found, Ok(2));
+
+        let not_found = v.binary_search_by(|(key, _)| key.cmp(&4));
+        assert_eq!(not_found, Err(2));
+    }
+
+    #[test]
+    fn test_retain_keeps_matching_elements_in_order() {
+        set_env();
+        let mut v = filled(b"r", 10);
+
+        v.retain(|x| x % 2 == 0);
+
+        assert_eq!(v.len(), 5);
+        let collected: Vec<i32> = (0..v.len()).map(|i| *v.get(i).unwrap()).collect();
+        assert_eq!(collected, vec![0, 2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn test_retain_drops_everything() {
+        set_env();
+        let mut v = filled(b"r2", 5);
+
+        v.retain(|_| false);
+
+        assert_eq!(v.len(), 0);
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_drain_full_consume_shifts_tail() {
+        set_env();
+        let mut v = filled(b"d", 5);
+
+        let drained: Vec<i32> = v.drain(1..3).collect();
+
+        assert_eq!(drained, vec![1, 2]);
+        assert_eq!(v.len(), 3);
+        assert_eq!(v.get(0), Some(&0));
+        assert_eq!(v.get(1), Some(&3));
+        assert_eq!(v.get(2), Some(&4));
+    }
+
+    #[test]
+    fn test_drain_dropped_early_still_shifts_tail() {
+        set_env();
+        let mut v = filled(b"d2", 5);
+
+        {
+            let mut drain = v.drain(1..3);
+            assert_eq!(drain.next(), Some(1));
+            // `drain` is dropped here without consuming the rest of the range; the drop guard
+            // must still remove index 2 and shift the tail down.
+        }
+
+        assert_eq!(v.len(), 3);
+        assert_eq!(v.get(0), Some(&0));
+        assert_eq!(v.get(1), Some(&3));
+        assert_eq!(v.get(2), Some(&4));
+    }
+
+    #[test]
+    fn test_drain_full_range() {
+        set_env();
+        let mut v = filled(b"d3", 3);
+
+        let drained: Vec<i32> = v.drain(..).collect();
+
+        assert_eq!(drained, vec![0, 1, 2]);
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_drain_empty_range_does_not_touch_tail() {
+        set_env();
+        let mut v = filled(b"d4", 5);
+
+        let drained: Vec<i32> = v.drain(3..3).collect();
+
+        assert!(drained.is_empty());
+        assert_eq!(v.len(), 5);
+        let collected: Vec<i32> = (0..v.len()).map(|i| *v.get(i).unwrap()).collect();
+        assert_eq!(collected, vec![0, 1, 2, 3, 4]);
+    }
 }